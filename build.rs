@@ -0,0 +1,71 @@
+use std::{env, fs, path::Path};
+
+/// Bakes every `words/*.txt` list into the binary so `FindPath --builtin <name>` works
+/// without a dictionnary file on disk. Each list is validated the way `find_path`
+/// validates a file loaded at runtime without `--distance`: ASCII-only, one word per
+/// line, all words the same length. A list of mixed lengths fails the build, even
+/// though `--distance` accepts such a file at runtime
+fn main() {
+    let words_dir = Path::new("words");
+    println!("cargo:rerun-if-changed=words");
+
+    let mut dictionaries = Vec::new();
+
+    if words_dir.is_dir() {
+        let mut paths: Vec<_> = fs::read_dir(words_dir)
+            .expect("failed to read words/ directory")
+            .map(|entry| entry.expect("failed to read words/ directory entry").path())
+            .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("txt"))
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            println!("cargo:rerun-if-changed={}", path.display());
+
+            let name = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or_else(|| panic!("{}: non UTF-8 word list name", path.display()))
+                .to_owned();
+
+            let contents = fs::read_to_string(&path)
+                .unwrap_or_else(|err| panic!("failed to read {}: {err}", path.display()));
+
+            let mut word_len = None;
+            for line in contents.lines().filter(|line| !line.is_empty()) {
+                if !line.is_ascii() {
+                    panic!(
+                        "{}: word '{line}' contains non-ASCII characters",
+                        path.display()
+                    );
+                }
+
+                match word_len {
+                    None => word_len = Some(line.len()),
+                    Some(len) if len != line.len() => panic!(
+                        "{}: words must all share the same length, offending word: '{line}'",
+                        path.display()
+                    ),
+                    Some(_) => {}
+                }
+            }
+
+            dictionaries.push((name, path));
+        }
+    }
+
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR not set");
+    let dest_path = Path::new(&out_dir).join("builtin_dictionaries.rs");
+
+    let mut generated = String::from("static BUILTIN_DICTIONARIES: &[(&str, &[u8])] = &[\n");
+    for (name, path) in &dictionaries {
+        // `include_bytes!` resolves relative paths against the generated file itself
+        // (which lives under `OUT_DIR`), so the path baked in here must be absolute
+        let absolute_path = fs::canonicalize(path)
+            .unwrap_or_else(|err| panic!("failed to canonicalize {}: {err}", path.display()));
+        generated.push_str(&format!("    ({name:?}, include_bytes!({absolute_path:?})),\n"));
+    }
+    generated.push_str("];\n");
+
+    fs::write(&dest_path, generated).expect("failed to write generated builtin dictionaries");
+}
@@ -2,10 +2,11 @@ use anyhow::{anyhow, bail};
 use clap::Parser;
 use fnv::{FnvHashMap, FnvHashSet};
 use memmap2::MmapOptions;
+use rand::{seq::SliceRandom, Rng, SeedableRng};
 use std::{
     collections::VecDeque,
     fs::{File, OpenOptions},
-    io::{BufRead, BufReader, BufWriter, Write},
+    io::{BufRead, BufReader, BufWriter, Read, Write},
     path::{Path, PathBuf},
 };
 
@@ -26,14 +27,95 @@ enum Command {
     },
     /// Find a path across two words
     FindPath {
-        words: PathBuf,
         start_word: String,
         end_word: String,
+
+        /// Dictionnary file to load. Mutually exclusive with `--builtin`
+        #[arg(long, conflicts_with = "builtin")]
+        words: Option<PathBuf>,
+
+        /// Use one of the dictionaries baked into the binary instead of a file on disk
+        #[arg(long, conflicts_with = "words")]
+        builtin: Option<String>,
+
+        /// Maximum edit distance between two neighboring words. When omitted, only the
+        /// legacy same-length single-substitution neighbors are considered
+        #[arg(long)]
+        distance: Option<usize>,
+
+        /// Edit operations to consider when computing neighbors with `--distance 1`.
+        /// Defaults to all four operations. Ignored for `--distance` greater than 1,
+        /// where the Levenshtein automaton always considers insertions, deletions and
+        /// substitutions
+        #[arg(long, value_enum, num_args = 1..)]
+        ops: Vec<EditOp>,
+
+        /// Suggest close dictionnary entries when `start_word` or `end_word` is missing
+        #[arg(long)]
+        suggest: bool,
+
+        /// Like `--suggest`, but automatically retries with the closest match
+        #[arg(long)]
+        auto_correct: bool,
+
+        /// Search outward from both `start_word` and `end_word` at once instead of a
+        /// single-source BFS, exploring far fewer nodes on large dictionaries
+        #[arg(long)]
+        bidirectional: bool,
+
+        /// Reconstruct every shortest ladder instead of just one
+        #[arg(long)]
+        all: bool,
+    },
+    /// Generate a random, guaranteed-solvable start/end pair
+    GeneratePuzzle {
+        /// Exact ladder length (BFS depth) between the generated start and end word
+        #[arg(long)]
+        length: usize,
+
+        /// Dictionnary file to load. Mutually exclusive with `--builtin`
+        #[arg(long, conflicts_with = "builtin")]
+        words: Option<PathBuf>,
+
+        /// Use one of the dictionaries baked into the binary instead of a file on disk
+        #[arg(long, conflicts_with = "words")]
+        builtin: Option<String>,
+
+        /// Maximum edit distance between two neighboring words, see `find-path --distance`
+        #[arg(long)]
+        distance: Option<usize>,
+
+        /// Edit operations to consider, see `find-path --ops`
+        #[arg(long, value_enum, num_args = 1..)]
+        ops: Vec<EditOp>,
+
+        /// Seed the RNG for a reproducible puzzle
+        #[arg(long)]
+        seed: Option<u64>,
     },
 }
 
 const ALPHA: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum EditOp {
+    Insert,
+    Delete,
+    Substitute,
+    Transpose,
+}
+
+impl EditOp {
+    fn all() -> &'static [EditOp] {
+        &[
+            EditOp::Insert,
+            EditOp::Delete,
+            EditOp::Substitute,
+            EditOp::Transpose,
+        ]
+    }
+}
+
 fn extract_words(words: &Path, extracted_words: &Path, len: usize) -> anyhow::Result<()> {
     let words = File::open(words)?;
     let reader = BufReader::new(words);
@@ -63,10 +145,84 @@ type Word<'a> = &'a [u8];
 type WordList<'a> = FnvHashSet<Word<'a>>;
 type Dictionnary<'a> = FnvHashMap<Word<'a>, WordList<'a>>;
 
-fn find_path(words: &Path, start_word: &str, end_word: &str) -> anyhow::Result<()> {
-    // read the words
-    let words = File::open(words)?;
-    let words = unsafe { MmapOptions::new().map(&words)? };
+// generates `static BUILTIN_DICTIONARIES: &[(&str, &[u8])]` from every `words/*.txt`
+// file, baked into the binary at compile time
+include!(concat!(env!("OUT_DIR"), "/builtin_dictionaries.rs"));
+
+/// Either an mmap'd plaintext file, an owned buffer decompressed from a `.gz`/`.bz2`
+/// file, or one of the `BUILTIN_DICTIONARIES` slices, so `find_path` can treat all
+/// three the same way once loaded
+enum WordsSource {
+    File(memmap2::Mmap),
+    Owned(Vec<u8>),
+    Builtin(&'static [u8]),
+}
+
+impl std::ops::Deref for WordsSource {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            WordsSource::File(mmap) => mmap,
+            WordsSource::Owned(buf) => buf,
+            WordsSource::Builtin(bytes) => bytes,
+        }
+    }
+}
+
+/// Detects whether `mmap` holds a gzip or bzip2 stream (by extension, falling back to
+/// magic bytes for misnamed files) and transparently decompresses it into an owned
+/// buffer. Plaintext files take the zero-copy mmap fast path untouched
+fn load_possibly_compressed(path: &Path, mmap: memmap2::Mmap) -> anyhow::Result<WordsSource> {
+    let extension = path.extension().and_then(|ext| ext.to_str());
+    let is_gzip = extension == Some("gz") || mmap.starts_with(&[0x1f, 0x8b]);
+    let is_bzip2 = extension == Some("bz2") || mmap.starts_with(b"BZh");
+
+    if is_gzip {
+        let mut buf = Vec::new();
+        flate2::read::GzDecoder::new(&mmap[..]).read_to_end(&mut buf)?;
+        Ok(WordsSource::Owned(buf))
+    } else if is_bzip2 {
+        let mut buf = Vec::new();
+        bzip2::read::BzDecoder::new(&mmap[..]).read_to_end(&mut buf)?;
+        Ok(WordsSource::Owned(buf))
+    } else {
+        Ok(WordsSource::File(mmap))
+    }
+}
+
+/// Loads a dictionnary, either from a file on disk (transparently decompressing
+/// `.gz`/`.bz2`) or from a baked-in `--builtin` list, and returns its raw bytes;
+/// [`parse_word_list`] then splits them into the [`WordList`] shared by every
+/// subcommand that operates on a dictionnary
+fn load_word_list(words: Option<&Path>, builtin: Option<&str>) -> anyhow::Result<WordsSource> {
+    match (words, builtin) {
+        (Some(words), None) => {
+            let file = File::open(words)?;
+            let mmap = unsafe { MmapOptions::new().map(&file)? };
+            load_possibly_compressed(words, mmap)
+        }
+        (None, Some(builtin)) => {
+            let bytes = BUILTIN_DICTIONARIES
+                .iter()
+                .find(|(name, _)| *name == builtin)
+                .map(|(_, bytes)| *bytes)
+                .ok_or_else(|| {
+                    let available = BUILTIN_DICTIONARIES
+                        .iter()
+                        .map(|(name, _)| *name)
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    anyhow!("no builtin dictionnary named '{builtin}', available: {available}")
+                })?;
+            Ok(WordsSource::Builtin(bytes))
+        }
+        (Some(_), Some(_)) => bail!("pass either a dictionnary file or --builtin, not both"),
+        (None, None) => bail!("pass a dictionnary file or --builtin"),
+    }
+}
+
+fn parse_word_list(words: &WordsSource, distance: Option<usize>) -> anyhow::Result<WordList<'_>> {
     let words = words
         .split(|&b| b == b'\n')
         .filter(|word| !word.is_empty())
@@ -79,86 +235,530 @@ fn find_path(words: &Path, start_word: &str, end_word: &str) -> anyhow::Result<(
         })
         .collect::<Result<WordList, _>>()?;
 
-    let words_len = if let Some(word) = words.iter().next() {
-        word.len()
-    } else {
-        bail!("no word in dictionnary")
-    };
+    if words.is_empty() {
+        bail!("no word in dictionnary");
+    }
+
+    // the uniform-length dictionnary is only a requirement of the legacy,
+    // same-length substitution mode: fuzzy distance ladders are explicitly
+    // meant to change word length
+    if distance.is_none() {
+        let words_len = words.iter().next().unwrap().len();
 
-    for word in words.iter() {
-        if word.len() != words_len {
-            bail!(
-                "dictionnary contains words of different lengths, offending word: '{}'",
-                std::str::from_utf8(word)?
-            );
+        for word in words.iter() {
+            if word.len() != words_len {
+                bail!(
+                    "dictionnary contains words of different lengths, offending word: '{}'",
+                    std::str::from_utf8(word)?
+                );
+            }
         }
     }
 
-    println!("{} words were loaded", words.len());
+    Ok(words)
+}
 
-    // generate the dictionnary
+/// Builds the neighbor adjacency map for `words`, dispatching to the legacy
+/// same-length substitution mode, the edit-distance-1 fast path, or the Levenshtein
+/// automaton, depending on `distance`
+fn build_dictionary<'a>(
+    words: &WordList<'a>,
+    distance: Option<usize>,
+    ops: &[EditOp],
+) -> Dictionnary<'a> {
     let mut dict = Dictionnary::default();
-    let mut buf = Vec::with_capacity(words_len);
 
-    for word in &words {
-        compute_neighbors(word, &words, &mut dict, &mut buf)?;
+    match distance {
+        None => {
+            let mut buf = Vec::new();
+            for word in words {
+                compute_neighbors(word, words, &mut dict, &mut buf);
+            }
+        }
+        Some(1) => {
+            let ops = if ops.is_empty() { EditOp::all() } else { ops };
+            let mut buf = Vec::new();
+            for word in words {
+                compute_neighbors_edit1(word, words, ops, &mut dict, &mut buf);
+            }
+        }
+        Some(max_distance) => {
+            let mut sorted_words: Vec<Word> = words.iter().copied().collect();
+            sorted_words.sort_unstable();
+
+            for word in words {
+                let neighbors = compute_neighbors_automaton(word, &sorted_words, max_distance);
+                dict.insert(word, neighbors);
+            }
+        }
+    }
+
+    dict
+}
+
+/// A seeded RNG for reproducible puzzles, or the OS RNG otherwise
+fn make_rng(seed: Option<u64>) -> Box<dyn rand::RngCore> {
+    match seed {
+        Some(seed) => Box::new(rand::rngs::StdRng::seed_from_u64(seed)),
+        None => Box::new(rand::rngs::OsRng),
+    }
+}
+
+/// BFS from `start`, returning every word found at exactly `length` steps away
+fn words_at_depth<'a>(dict: &Dictionnary<'a>, start: Word<'a>, length: usize) -> Vec<Word<'a>> {
+    let mut depth = FnvHashMap::with_capacity_and_hasher(1, Default::default());
+    depth.insert(start, 0usize);
+
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(current) = queue.pop_front() {
+        let current_depth = depth[current];
+
+        if current_depth >= length {
+            continue;
+        }
+
+        let Some(neighbors) = dict.get(current) else {
+            continue;
+        };
+
+        for &neighbor in neighbors {
+            if !depth.contains_key(neighbor) {
+                depth.insert(neighbor, current_depth + 1);
+                queue.push_back(neighbor);
+            }
+        }
+    }
+
+    depth
+        .into_iter()
+        .filter(|&(_, found_at)| found_at == length)
+        .map(|(word, _)| word)
+        .collect()
+}
+
+fn generate_puzzle(
+    words: Option<&Path>,
+    builtin: Option<&str>,
+    length: usize,
+    distance: Option<usize>,
+    ops: &[EditOp],
+    seed: Option<u64>,
+) -> anyhow::Result<()> {
+    let source = load_word_list(words, builtin)?;
+    let word_list = parse_word_list(&source, distance)?;
+
+    println!("{} words were loaded", word_list.len());
+
+    let dict = build_dictionary(&word_list, distance, ops);
+    let mut rng = make_rng(seed);
+
+    // not every word has one exactly `length` steps away: shuffle the candidate start
+    // words and try each in turn until one actually yields a solvable pair
+    let mut start_candidates: Vec<Word> = word_list.iter().copied().collect();
+    start_candidates.shuffle(rng.as_mut());
+
+    for start_word in start_candidates {
+        let candidates = words_at_depth(&dict, start_word, length);
+
+        if candidates.is_empty() {
+            continue;
+        }
+
+        let end_word = candidates[rng.gen_range(0..candidates.len())];
+
+        println!("start word: {}", std::str::from_utf8(start_word)?);
+        println!("end word:   {}", std::str::from_utf8(end_word)?);
+
+        return Ok(());
     }
 
+    bail!("no pair of words in the dictionnary is exactly {length} step(s) apart")
+}
+
+/// Knobs of `FindPath` beyond the dictionnary and the two words themselves, bundled
+/// together so `find_path` stays under clippy's argument-count lint
+struct FindPathOptions<'a> {
+    distance: Option<usize>,
+    ops: &'a [EditOp],
+    suggest: bool,
+    auto_correct: bool,
+    bidirectional: bool,
+    all: bool,
+}
+
+fn find_path(
+    words: Option<&Path>,
+    builtin: Option<&str>,
+    start_word: &str,
+    end_word: &str,
+    options: &FindPathOptions,
+) -> anyhow::Result<()> {
+    let source = load_word_list(words, builtin)?;
+    let words = parse_word_list(&source, options.distance)?;
+
+    println!("{} words were loaded", words.len());
+
+    let dict = build_dictionary(&words, options.distance, options.ops);
+
     drop(words);
 
     // find the path
-    let start_word = start_word.as_bytes();
-    let end_word = end_word.as_bytes();
+    let Some(start_word) = resolve_word(
+        "start word",
+        start_word,
+        &dict,
+        options.suggest,
+        options.auto_correct,
+    )? else {
+        return Ok(());
+    };
+    let Some(end_word) = resolve_word(
+        "end word",
+        end_word,
+        &dict,
+        options.suggest,
+        options.auto_correct,
+    )? else {
+        return Ok(());
+    };
+
+    let ladders = if options.bidirectional {
+        bidirectional_paths(&dict, start_word, end_word, options.all)?
+    } else {
+        single_source_paths(&dict, start_word, end_word, options.all)?
+    };
+
+    match ladders {
+        None => println!("no path found"),
+        Some(ladders) => {
+            if options.all {
+                println!("found {} shortest path(s):", ladders.len());
+            }
+
+            for ladder in &ladders {
+                println!("found path:");
+                for &word in ladder {
+                    println!("  - {}", std::str::from_utf8(word)?);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Expands one BFS level: visits every unexplored neighbor of `frontier`, records it
+/// at the next depth, and appends it to `next_frontier`. With `all`, a node reached by
+/// more than one frontier word at the same depth keeps every such predecessor instead
+/// of only the first, so every shortest path (not just one) can later be reconstructed
+fn expand_level<'a>(
+    dict: &Dictionnary<'a>,
+    frontier: &[Word<'a>],
+    dist: &mut FnvHashMap<Word<'a>, usize>,
+    previous: &mut FnvHashMap<Word<'a>, Vec<Word<'a>>>,
+    all: bool,
+) -> Vec<Word<'a>> {
+    let mut next_frontier = Vec::new();
 
-    for word in [start_word, end_word] {
-        if !dict.contains_key(word) {
-            println!("'{}' is not in the dictionnary", std::str::from_utf8(word)?);
+    for &word in frontier {
+        let current_depth = dist[word];
 
-            return Ok(());
+        let Some(neighbors) = dict.get(word) else {
+            continue;
+        };
+
+        for &neighbor in neighbors {
+            match dist.get(neighbor) {
+                None => {
+                    dist.insert(neighbor, current_depth + 1);
+                    previous.insert(neighbor, vec![word]);
+                    next_frontier.push(neighbor);
+                }
+                Some(&neighbor_depth) if all && neighbor_depth == current_depth + 1 => {
+                    previous.get_mut(neighbor).unwrap().push(word);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    next_frontier
+}
+
+/// Walks every predecessor chain from `node` back to `start` recorded in `previous`,
+/// appending each complete (start-to-node, in order) path found to `out`
+fn enumerate_paths<'a>(
+    node: Word<'a>,
+    start: Word<'a>,
+    previous: &FnvHashMap<Word<'a>, Vec<Word<'a>>>,
+    suffix: &mut Vec<Word<'a>>,
+    out: &mut Vec<Vec<Word<'a>>>,
+) {
+    suffix.push(node);
+
+    if node == start {
+        let mut path = suffix.clone();
+        path.reverse();
+        out.push(path);
+    } else if let Some(predecessors) = previous.get(node) {
+        for &predecessor in predecessors {
+            enumerate_paths(predecessor, start, previous, suffix, out);
+        }
+    }
+
+    suffix.pop();
+}
+
+/// Single-source BFS from `start`, expanded one full level at a time so ties at the
+/// same depth are all recorded when `all` is set. Returns every shortest ladder from
+/// `start` to `end` (just one when `all` is false), or `None` if they aren't connected
+fn single_source_paths<'a>(
+    dict: &Dictionnary<'a>,
+    start: Word<'a>,
+    end: Word<'a>,
+    all: bool,
+) -> anyhow::Result<Option<Vec<Vec<Word<'a>>>>> {
+    let mut dist = FnvHashMap::with_capacity_and_hasher(1, Default::default());
+    dist.insert(start, 0usize);
+
+    let mut previous: FnvHashMap<Word, Vec<Word>> =
+        FnvHashMap::with_capacity_and_hasher(1, Default::default());
+
+    let mut frontier = vec![start];
+
+    while !frontier.is_empty() && !dist.contains_key(end) {
+        frontier = expand_level(dict, &frontier, &mut dist, &mut previous, all);
+    }
+
+    if !dist.contains_key(end) {
+        return Ok(None);
+    }
+
+    let mut ladders = Vec::new();
+    enumerate_paths(end, start, &previous, &mut Vec::new(), &mut ladders);
+
+    if !all {
+        ladders.truncate(1);
+    }
+
+    Ok(Some(ladders))
+}
+
+/// Bidirectional BFS: alternately expands the smaller of the `start`/`end` frontiers
+/// (each expansion covers a full level) until they meet, which explores far fewer
+/// nodes than a single-source search on large same-length dictionaries. Once the
+/// frontiers first touch, each side is given one more level so splits of the same
+/// total length reachable from the other direction aren't missed, then every meeting
+/// node tied for the shortest total is stitched into a full start-to-end ladder
+fn bidirectional_paths<'a>(
+    dict: &Dictionnary<'a>,
+    start: Word<'a>,
+    end: Word<'a>,
+    all: bool,
+) -> anyhow::Result<Option<Vec<Vec<Word<'a>>>>> {
+    if start == end {
+        return Ok(Some(vec![vec![start]]));
+    }
+
+    let mut fwd_dist = FnvHashMap::with_capacity_and_hasher(1, Default::default());
+    let mut bwd_dist = FnvHashMap::with_capacity_and_hasher(1, Default::default());
+    let mut fwd_prev: FnvHashMap<Word, Vec<Word>> =
+        FnvHashMap::with_capacity_and_hasher(1, Default::default());
+    let mut bwd_prev: FnvHashMap<Word, Vec<Word>> =
+        FnvHashMap::with_capacity_and_hasher(1, Default::default());
+
+    fwd_dist.insert(start, 0usize);
+    bwd_dist.insert(end, 0usize);
+
+    let mut fwd_frontier = vec![start];
+    let mut bwd_frontier = vec![end];
+
+    let frontiers_meet = |fwd_dist: &FnvHashMap<Word, usize>, bwd_dist: &FnvHashMap<Word, usize>| {
+        fwd_dist.keys().any(|word| bwd_dist.contains_key(word))
+    };
+
+    while !(fwd_frontier.is_empty() && bwd_frontier.is_empty()) {
+        if !bwd_frontier.is_empty()
+            && (fwd_frontier.is_empty() || bwd_frontier.len() < fwd_frontier.len())
+        {
+            bwd_frontier = expand_level(dict, &bwd_frontier, &mut bwd_dist, &mut bwd_prev, all);
+        } else {
+            fwd_frontier = expand_level(dict, &fwd_frontier, &mut fwd_dist, &mut fwd_prev, all);
+        }
+
+        if frontiers_meet(&fwd_dist, &bwd_dist) {
+            break;
+        }
+    }
+
+    if frontiers_meet(&fwd_dist, &bwd_dist) {
+        if !bwd_frontier.is_empty() {
+            expand_level(dict, &bwd_frontier, &mut bwd_dist, &mut bwd_prev, all);
+        }
+        if !fwd_frontier.is_empty() {
+            expand_level(dict, &fwd_frontier, &mut fwd_dist, &mut fwd_prev, all);
         }
     }
 
-    let mut path = VecDeque::from([start_word]);
-    let mut used = WordList::with_capacity_and_hasher(1, Default::default());
-    used.insert(start_word);
+    let Some(total) = fwd_dist
+        .iter()
+        .filter_map(|(word, &d)| bwd_dist.get(word).map(|&bd| d + bd))
+        .min()
+    else {
+        return Ok(None);
+    };
+
+    let meeting_nodes: Vec<Word> = fwd_dist
+        .iter()
+        .filter(|&(word, &d)| bwd_dist.get(word).is_some_and(|&bd| d + bd == total))
+        .map(|(&word, _)| word)
+        .collect();
 
-    let mut previous = FnvHashMap::with_capacity_and_hasher(1, Default::default());
-    previous.insert(start_word, Word::default());
+    let mut ladders = Vec::new();
 
-    while !path.is_empty() {
-        let current_word = path.pop_front().ok_or(anyhow!("path was empty???"))?;
+    for meeting in meeting_nodes {
+        let mut fwd_halves = Vec::new();
+        enumerate_paths(meeting, start, &fwd_prev, &mut Vec::new(), &mut fwd_halves);
 
-        let neighbors = dict
-            .get(current_word)
-            .ok_or(anyhow!("value not in dict???"))?;
+        let mut bwd_halves = Vec::new();
+        enumerate_paths(meeting, end, &bwd_prev, &mut Vec::new(), &mut bwd_halves);
 
-        for neighbor in neighbors {
-            if !used.contains(neighbor) {
-                used.insert(neighbor);
-                path.push_back(neighbor);
-                previous.insert(neighbor, current_word);
+        for fwd_half in &fwd_halves {
+            for bwd_half in &bwd_halves {
+                let mut ladder = fwd_half.clone();
+                ladder.extend(bwd_half.iter().rev().skip(1).copied());
+                ladders.push(ladder);
             }
         }
+
+        if !all {
+            break;
+        }
     }
 
-    if !used.contains(end_word) {
-        println!("no path found");
+    if !all {
+        ladders.truncate(1);
     } else {
-        let mut value = end_word;
-        let mut reverse_path = Vec::new();
-        while !value.is_empty() {
-            reverse_path.push(value);
-            value = previous[value];
+        // distinct meeting nodes can stitch into the identical ladder when both
+        // halves share the same tie-break; deduplicate before returning
+        ladders.sort_unstable();
+        ladders.dedup();
+    }
+
+    Ok(Some(ladders))
+}
+
+/// Distance cap used when scanning for "did you mean" suggestions: suggestions more
+/// than this far from the lookup are not worth the DP cost of finding
+const SUGGESTION_DISTANCE_CAP: usize = 2;
+/// Number of suggestions printed to the user
+const SUGGESTION_LIMIT: usize = 5;
+
+/// Looks `word` up in `dict`, returning its owned dictionnary key on success. If it's
+/// missing and `suggest` or `auto_correct` is set, prints the closest dictionnary
+/// entries by edit distance; with `auto_correct`, transparently retries with the best
+/// match instead of giving up
+fn resolve_word<'a>(
+    label: &str,
+    word: &str,
+    dict: &Dictionnary<'a>,
+    suggest: bool,
+    auto_correct: bool,
+) -> anyhow::Result<Option<Word<'a>>> {
+    let bytes = word.as_bytes();
+
+    if let Some((&key, _)) = dict.get_key_value(bytes) {
+        return Ok(Some(key));
+    }
+
+    println!("'{word}' is not in the dictionnary ({label})");
+
+    if !suggest && !auto_correct {
+        return Ok(None);
+    }
+
+    let suggestions = find_suggestions(
+        bytes,
+        dict.keys().copied(),
+        SUGGESTION_DISTANCE_CAP,
+        SUGGESTION_LIMIT,
+    );
+
+    if suggestions.is_empty() {
+        println!("  no close match found");
+        return Ok(None);
+    }
+
+    println!("  did you mean:");
+    for (distance, candidate) in &suggestions {
+        println!("    - {} (distance {distance})", std::str::from_utf8(candidate)?);
+    }
+
+    if auto_correct {
+        let (_, best) = suggestions[0];
+        println!("  auto-correcting to '{}'", std::str::from_utf8(best)?);
+        return Ok(Some(best));
+    }
+
+    Ok(None)
+}
+
+/// Computes the Levenshtein distance between `a` and `b`, bailing out early (returning
+/// `None`) as soon as every entry of the running DP row exceeds `cap`, and without
+/// ever starting the DP when the length difference alone already exceeds it
+fn edit_distance_capped(a: &[u8], b: &[u8], cap: usize) -> Option<usize> {
+    if a.len().abs_diff(b.len()) > cap {
+        return None;
+    }
+
+    let mut previous_row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, &ca) in a.iter().enumerate() {
+        let mut current_row = Vec::with_capacity(b.len() + 1);
+        current_row.push(i + 1);
+
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution_cost = usize::from(ca != cb);
+
+            let cost = (previous_row[j] + substitution_cost)
+                .min(previous_row[j + 1] + 1)
+                .min(current_row[j] + 1);
+
+            current_row.push(cost);
         }
 
-        println!("found path:");
-        for part in reverse_path.into_iter().rev() {
-            let part_str = std::str::from_utf8(part)?;
-            println!("  - {part_str}");
+        if current_row.iter().copied().min().unwrap() > cap {
+            return None;
         }
+
+        previous_row = current_row;
     }
 
-    Ok(())
+    let distance = previous_row[b.len()];
+    (distance <= cap).then_some(distance)
+}
+
+/// Scans `candidates` for the entries closest to `word`, capping the edit distance DP
+/// at `cap` and keeping only the `limit` best, sorted by distance then lexically
+fn find_suggestions<'a>(
+    word: &[u8],
+    candidates: impl Iterator<Item = Word<'a>>,
+    cap: usize,
+    limit: usize,
+) -> Vec<(usize, Word<'a>)> {
+    let mut suggestions: Vec<(usize, Word<'a>)> = candidates
+        .filter_map(|candidate| {
+            edit_distance_capped(word, candidate, cap).map(|distance| (distance, candidate))
+        })
+        .collect();
+
+    suggestions.sort_unstable_by(|(distance_a, a), (distance_b, b)| {
+        distance_a.cmp(distance_b).then_with(|| a.cmp(b))
+    });
+    suggestions.truncate(limit);
+
+    suggestions
 }
 
 fn compute_neighbors<'a>(
@@ -166,7 +766,7 @@ fn compute_neighbors<'a>(
     available_words: &WordList<'a>,
     dict: &mut Dictionnary<'a>,
     buf: &mut Vec<u8>,
-) -> anyhow::Result<()> {
+) {
     let mut neighbors = WordList::default();
 
     buf.clear();
@@ -191,10 +791,194 @@ fn compute_neighbors<'a>(
     }
 
     dict.insert(word, neighbors);
+}
 
-    Ok(())
+/// Computes the neighbors of `word` within edit distance 1, by enumerating every
+/// candidate edit allowed by `ops` (substitutions, insertions, deletions and adjacent
+/// transpositions) and probing `available_words` for a match. This is cheap enough to
+/// do exhaustively and avoids paying for a full Levenshtein automaton at `k = 1`
+fn compute_neighbors_edit1<'a>(
+    word: Word<'a>,
+    available_words: &WordList<'a>,
+    ops: &[EditOp],
+    dict: &mut Dictionnary<'a>,
+    buf: &mut Vec<u8>,
+) {
+    let mut neighbors = WordList::default();
+
+    if ops.contains(&EditOp::Substitute) {
+        buf.clear();
+        buf.extend_from_slice(word);
+
+        for idx in 0..buf.len() {
+            for &letter in ALPHA {
+                let original_letter = buf[idx];
+
+                if original_letter == letter {
+                    continue;
+                }
+
+                buf[idx] = letter;
+
+                if let Some(neighbor) = available_words.get(buf.as_slice()) {
+                    neighbors.insert(*neighbor);
+                }
+
+                buf[idx] = original_letter;
+            }
+        }
+    }
+
+    if ops.contains(&EditOp::Insert) {
+        for idx in 0..=word.len() {
+            buf.clear();
+            buf.extend_from_slice(&word[..idx]);
+
+            for &letter in ALPHA {
+                buf.push(letter);
+                buf.extend_from_slice(&word[idx..]);
+
+                if let Some(neighbor) = available_words.get(buf.as_slice()) {
+                    neighbors.insert(*neighbor);
+                }
+
+                buf.truncate(idx);
+            }
+        }
+    }
+
+    if ops.contains(&EditOp::Delete) && word.len() > 1 {
+        for idx in 0..word.len() {
+            buf.clear();
+            buf.extend_from_slice(&word[..idx]);
+            buf.extend_from_slice(&word[idx + 1..]);
+
+            if let Some(neighbor) = available_words.get(buf.as_slice()) {
+                neighbors.insert(*neighbor);
+            }
+        }
+    }
+
+    if ops.contains(&EditOp::Transpose) && word.len() > 1 {
+        buf.clear();
+        buf.extend_from_slice(word);
+
+        for idx in 0..buf.len() - 1 {
+            buf.swap(idx, idx + 1);
+
+            if let Some(neighbor) = available_words.get(buf.as_slice()) {
+                neighbors.insert(*neighbor);
+            }
+
+            buf.swap(idx, idx + 1);
+        }
+    }
+
+    neighbors.remove(word);
+    dict.insert(word, neighbors);
+}
+
+/// A Levenshtein automaton for `pattern`: a DFA whose state is the DP row of edit
+/// distances between the input consumed so far and every prefix of `pattern`, fed one
+/// character at a time. A branch is dead once every offset in the row exceeds
+/// `max_distance`, which lets [`compute_neighbors_automaton`] skip whole dictionary
+/// prefixes instead of computing a full edit distance for every word
+struct LevenshteinAutomaton<'a> {
+    pattern: Word<'a>,
+    max_distance: usize,
+}
+
+impl<'a> LevenshteinAutomaton<'a> {
+    fn new(pattern: Word<'a>, max_distance: usize) -> Self {
+        Self {
+            pattern,
+            max_distance,
+        }
+    }
+
+    fn start(&self) -> Vec<usize> {
+        (0..=self.pattern.len()).collect()
+    }
+
+    /// Feeds one more input character and returns the next row, or `None` if every
+    /// offset in the resulting row exceeds `max_distance` (dead branch, prune it)
+    fn step(&self, row: &[usize], c: u8) -> Option<Vec<usize>> {
+        let mut next_row = Vec::with_capacity(row.len());
+        next_row.push(row[0] + 1);
+
+        for j in 1..row.len() {
+            let substitution_cost = if self.pattern[j - 1] == c { 0 } else { 1 };
+
+            let cost = (row[j] + 1)
+                .min(next_row[j - 1] + 1)
+                .min(row[j - 1] + substitution_cost);
+
+            next_row.push(cost);
+        }
+
+        if next_row.iter().copied().min().unwrap() > self.max_distance {
+            None
+        } else {
+            Some(next_row)
+        }
+    }
+
+    fn is_match(&self, row: &[usize]) -> bool {
+        row.last().is_some_and(|&distance| distance <= self.max_distance)
+    }
 }
 
+/// Finds every word of `sorted_words` within `max_distance` of `word`, by walking the
+/// automaton over the (lexicographically sorted) dictionary and reusing the DP row
+/// computed for the longest shared prefix between consecutive words, so a dead branch
+/// is only ever explored once
+fn compute_neighbors_automaton<'a>(
+    word: Word<'a>,
+    sorted_words: &[Word<'a>],
+    max_distance: usize,
+) -> WordList<'a> {
+    let automaton = LevenshteinAutomaton::new(word, max_distance);
+    let mut neighbors = WordList::default();
+
+    // rows[i] holds the automaton state after consuming `prefix[..i]` of the
+    // previously processed word; `prefix_len` is how much of that state is still
+    // valid for the current word
+    let mut rows: Vec<Vec<usize>> = vec![automaton.start()];
+    let mut previous: Word = &[];
+
+    for &candidate in sorted_words {
+        let shared = previous
+            .iter()
+            .zip(candidate.iter())
+            .take_while(|(a, b)| a == b)
+            .count()
+            // a previous branch may have died before reaching `shared`: the rows
+            // stack only ever covers a prefix of `previous` up to where it was
+            // pruned, so that's the real starting point for the shared state
+            .min(rows.len() - 1);
+        rows.truncate(shared + 1);
+
+        for &c in &candidate[shared..] {
+            match automaton.step(rows.last().unwrap(), c) {
+                Some(next_row) => rows.push(next_row),
+                None => break,
+            }
+        }
+
+        if rows.len() == candidate.len() + 1
+            && candidate != word
+            && automaton.is_match(rows.last().unwrap())
+        {
+            neighbors.insert(candidate);
+        }
+
+        previous = candidate;
+    }
+
+    neighbors
+}
+
+
 fn main() {
     let command = Command::parse();
 
@@ -206,8 +990,205 @@ fn main() {
         } => extract_words(&words, &extracted_words, len).unwrap(),
         Command::FindPath {
             words,
+            builtin,
             start_word,
             end_word,
-        } => find_path(&words, &start_word, &end_word).unwrap(),
+            distance,
+            ops,
+            suggest,
+            auto_correct,
+            bidirectional,
+            all,
+        } => find_path(
+            words.as_deref(),
+            builtin.as_deref(),
+            &start_word,
+            &end_word,
+            &FindPathOptions {
+                distance,
+                ops: &ops,
+                suggest,
+                auto_correct,
+                bidirectional,
+                all,
+            },
+        )
+        .unwrap(),
+        Command::GeneratePuzzle {
+            words,
+            builtin,
+            length,
+            distance,
+            ops,
+            seed,
+        } => generate_puzzle(
+            words.as_deref(),
+            builtin.as_deref(),
+            length,
+            distance,
+            &ops,
+            seed,
+        )
+        .unwrap(),
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Plain O(n*m) Levenshtein distance, used as the reference implementation the
+    /// automaton's pruning is checked against
+    fn brute_force_distance(a: &[u8], b: &[u8]) -> usize {
+        let mut previous_row: Vec<usize> = (0..=b.len()).collect();
+        for (i, &ca) in a.iter().enumerate() {
+            let mut current_row = vec![i + 1];
+            for (j, &cb) in b.iter().enumerate() {
+                let substitution_cost = usize::from(ca != cb);
+                let cost = (previous_row[j] + substitution_cost)
+                    .min(previous_row[j + 1] + 1)
+                    .min(current_row[j] + 1);
+                current_row.push(cost);
+            }
+            previous_row = current_row;
+        }
+        previous_row[b.len()]
+    }
+
+    #[test]
+    fn automaton_matches_brute_force_distance() {
+        let words: Vec<&[u8]> = vec![
+            b"cat", b"cats", b"cut", b"cot", b"coats", b"dog", b"dogs", b"cart", b"car", b"at",
+        ];
+        let mut sorted_words = words.clone();
+        sorted_words.sort_unstable();
+
+        for max_distance in 1..=2 {
+            for &word in &words {
+                let neighbors = compute_neighbors_automaton(word, &sorted_words, max_distance);
+                let expected: WordList = words
+                    .iter()
+                    .copied()
+                    .filter(|&candidate| {
+                        candidate != word && brute_force_distance(word, candidate) <= max_distance
+                    })
+                    .collect();
+                assert_eq!(
+                    neighbors,
+                    expected,
+                    "distance {max_distance}, word {:?}",
+                    std::str::from_utf8(word)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn bidirectional_matches_single_source_with_ties() {
+        // a diamond: two disjoint shortest paths of length 2 tie between "a" and "d",
+        // exercising the `--all` multi-predecessor stitching and dedup
+        let mut dict: Dictionnary<'static> = Dictionnary::default();
+        dict.insert(b"a", [b"b".as_slice(), b"c".as_slice()].into_iter().collect());
+        dict.insert(b"b", [b"a".as_slice(), b"d".as_slice()].into_iter().collect());
+        dict.insert(b"c", [b"a".as_slice(), b"d".as_slice()].into_iter().collect());
+        dict.insert(b"d", [b"b".as_slice(), b"c".as_slice()].into_iter().collect());
+
+        let mut single = single_source_paths(&dict, b"a", b"d", true).unwrap().unwrap();
+        let mut bidirectional = bidirectional_paths(&dict, b"a", b"d", true).unwrap().unwrap();
+
+        single.sort_unstable();
+        bidirectional.sort_unstable();
+
+        assert_eq!(single.len(), 2);
+        assert_eq!(single, bidirectional);
+    }
+
+    #[test]
+    fn words_at_depth_finds_exact_distance() {
+        // a -> b -> c -> d, a straight chain: only `b` is 1 step from `a`, only `c` is
+        // 2 steps, and `d` (3 steps) must not show up at depth 2
+        let mut dict: Dictionnary<'static> = Dictionnary::default();
+        dict.insert(b"a", [b"b".as_slice()].into_iter().collect());
+        dict.insert(b"b", [b"a".as_slice(), b"c".as_slice()].into_iter().collect());
+        dict.insert(b"c", [b"b".as_slice(), b"d".as_slice()].into_iter().collect());
+        dict.insert(b"d", [b"c".as_slice()].into_iter().collect());
+
+        assert_eq!(words_at_depth(&dict, b"a", 1), vec![b"b".as_slice()]);
+        assert_eq!(words_at_depth(&dict, b"a", 2), vec![b"c".as_slice()]);
+        assert!(words_at_depth(&dict, b"a", 5).is_empty());
+    }
+
+    #[test]
+    fn same_seed_shuffles_start_candidates_identically() {
+        // generate_puzzle's retry loop relies on `make_rng(Some(seed))` being fully
+        // reproducible: same seed, same shuffle order, same gen_range picks
+        let mut words: Vec<Word<'static>> = vec![
+            b"cat".as_slice(),
+            b"cut".as_slice(),
+            b"cot".as_slice(),
+            b"cart".as_slice(),
+            b"car".as_slice(),
+            b"dog".as_slice(),
+            b"dogs".as_slice(),
+        ];
+
+        let mut first = words.clone();
+        first.shuffle(make_rng(Some(42)).as_mut());
+
+        let mut second = words.clone();
+        second.shuffle(make_rng(Some(42)).as_mut());
+
+        assert_eq!(first, second);
+
+        words.shuffle(make_rng(Some(7)).as_mut());
+        assert_ne!(first, words, "different seeds should (almost always) diverge");
+    }
+
+    #[test]
+    fn edit_distance_capped_matches_uncapped_distance() {
+        assert_eq!(edit_distance_capped(b"cat", b"cat", 2), Some(0));
+        assert_eq!(edit_distance_capped(b"cat", b"cot", 2), Some(1));
+        assert_eq!(edit_distance_capped(b"cat", b"dogs", 2), None);
+        // length difference alone already exceeds the cap
+        assert_eq!(edit_distance_capped(b"cat", b"category", 2), None);
+    }
+
+    #[test]
+    fn find_suggestions_ranks_by_distance_then_lexically_and_respects_limit() {
+        let candidates: WordList = [b"cat".as_slice(), b"cot", b"cut", b"cart", b"dog"]
+            .into_iter()
+            .collect();
+
+        let suggestions = find_suggestions(b"cat", candidates.iter().copied(), 1, 2);
+
+        // "cat" itself is distance 0, "cart"/"cot"/"cut" are all distance 1 and
+        // tie-broken lexically so only "cart" fits the limit; "dog" exceeds the cap
+        assert_eq!(
+            suggestions,
+            vec![(0, b"cat".as_slice()), (1, b"cart".as_slice())]
+        );
+    }
+
+    #[test]
+    fn load_possibly_compressed_round_trips_gzip() {
+        use std::io::Write as _;
+
+        let payload = b"about\nabove\nabuse\n";
+
+        let mut encoded = Vec::new();
+        flate2::write::GzEncoder::new(&mut encoded, flate2::Compression::default())
+            .write_all(payload)
+            .unwrap();
+
+        let path = std::env::temp_dir().join(format!("wordpathgame_test_{}.gz", std::process::id()));
+        std::fs::write(&path, &encoded).unwrap();
+
+        let file = File::open(&path).unwrap();
+        let mmap = unsafe { MmapOptions::new().map(&file).unwrap() };
+        let source = load_possibly_compressed(&path, mmap).unwrap();
+
+        assert_eq!(&*source, payload);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}